@@ -0,0 +1,158 @@
+/// A tape cell of some fixed bit width, with wrapping arithmetic and the
+/// byte conversions the interpreter needs for `,`/`.` and for applying
+/// `MulAdd`. Implemented for `u8`, `u16`, and `u32` so the interpreter can be
+/// instantiated at whichever width `--cell-size` selects.
+pub trait Cell: Copy + Default + PartialEq + 'static {
+    const BYTES: usize;
+    const WIDTH: i64;
+    const MAX: Self;
+
+    fn as_i64(self) -> i64;
+    fn from_i64(v: i64) -> Self;
+
+    /// Adds a signed delta, wrapping at this cell's width. Used for both
+    /// `Add`/`Sub` (delta is the run-length, possibly negative) and
+    /// `MulAdd` (delta is `factor * tape[pos]`).
+    fn wrapping_offset(self, delta: i64) -> Self {
+        Self::from_i64((self.as_i64() + delta).rem_euclid(Self::WIDTH))
+    }
+
+    fn is_zero(self) -> bool {
+        self == Self::default()
+    }
+
+    fn low_byte(self) -> u8;
+    fn from_low_byte(b: u8) -> Self;
+
+    fn to_le_bytes(self) -> Vec<u8>;
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Index of the first zero cell at or after `start` in `chunk`, if any.
+    /// Overridden for `u8` to use `memchr`.
+    fn find_zero_from(chunk: &[Self], start: usize) -> Option<usize> {
+        chunk[start..].iter().position(|c| c.is_zero())
+    }
+
+    /// Index of the last zero cell at or before `end_inclusive` in `chunk`,
+    /// if any. Overridden for `u8` to use `memchr`.
+    fn find_zero_before(chunk: &[Self], end_inclusive: usize) -> Option<usize> {
+        chunk[..=end_inclusive].iter().rposition(|c| c.is_zero())
+    }
+}
+
+impl Cell for u8 {
+    const BYTES: usize = 1;
+    const WIDTH: i64 = 1 << 8;
+    const MAX: Self = u8::MAX;
+
+    fn as_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn from_i64(v: i64) -> Self {
+        v as u8
+    }
+
+    fn low_byte(self) -> u8 {
+        self
+    }
+
+    fn from_low_byte(b: u8) -> Self {
+        b
+    }
+
+    fn to_le_bytes(self) -> Vec<u8> {
+        vec![self]
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    fn find_zero_from(chunk: &[Self], start: usize) -> Option<usize> {
+        memchr::memchr(0, &chunk[start..])
+    }
+
+    fn find_zero_before(chunk: &[Self], end_inclusive: usize) -> Option<usize> {
+        memchr::memrchr(0, &chunk[..=end_inclusive])
+    }
+}
+
+impl Cell for u16 {
+    const BYTES: usize = 2;
+    const WIDTH: i64 = 1 << 16;
+    const MAX: Self = u16::MAX;
+
+    fn as_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn from_i64(v: i64) -> Self {
+        v as u16
+    }
+
+    fn low_byte(self) -> u8 {
+        self as u8
+    }
+
+    fn from_low_byte(b: u8) -> Self {
+        b as u16
+    }
+
+    fn to_le_bytes(self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        u16::from_le_bytes([bytes[0], bytes[1]])
+    }
+}
+
+impl Cell for u32 {
+    const BYTES: usize = 4;
+    const WIDTH: i64 = 1 << 32;
+    const MAX: Self = u32::MAX;
+
+    fn as_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn from_i64(v: i64) -> Self {
+        v as u32
+    }
+
+    fn low_byte(self) -> u8 {
+        self as u8
+    }
+
+    fn from_low_byte(b: u8) -> Self {
+        b as u32
+    }
+
+    fn to_le_bytes(self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_offset_wraps_at_each_width() {
+        assert_eq!(0u8.wrapping_offset(-1), 255);
+        assert_eq!(255u8.wrapping_offset(1), 0);
+        assert_eq!(0u16.wrapping_offset(-1), 65535);
+        assert_eq!(0u32.wrapping_offset(-1), u32::MAX);
+    }
+
+    #[test]
+    fn byte_round_trips() {
+        assert_eq!(u16::from_le_bytes(0x1234u16.to_le_bytes()), 0x1234);
+        assert_eq!(u32::from_le_bytes(0xdeadbeefu32.to_le_bytes()), 0xdeadbeef);
+    }
+}
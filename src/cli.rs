@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, Command};
+
+use crate::emit::Lang;
+use crate::interp::{EofBehavior, Options};
+
+/// Which `Cell` type the interpreter should be instantiated with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellWidth {
+    Eight,
+    Sixteen,
+    ThirtyTwo,
+}
+
+impl CellWidth {
+    pub fn bits(self) -> u32 {
+        match self {
+            CellWidth::Eight => 8,
+            CellWidth::Sixteen => 16,
+            CellWidth::ThirtyTwo => 32,
+        }
+    }
+}
+
+/// Parsed command-line arguments for the interpreter binary.
+pub struct Cli {
+    pub paths: Vec<PathBuf>,
+    pub cell_width: CellWidth,
+    pub options: Options,
+    /// Forces the "Running {path}" banner on, even for a single file.
+    pub verbose: bool,
+    /// Forces the "Running {path}" banner off, even when running multiple
+    /// files. Mutually exclusive with `verbose`.
+    pub quiet: bool,
+    /// When set, the program is transpiled to this source language and
+    /// printed to stdout instead of being interpreted.
+    pub emit: Option<Lang>,
+}
+
+pub fn parse() -> Cli {
+    let matches = Command::new("brainrust")
+        .about("A Brainfuck interpreter")
+        .arg(
+            Arg::new("path")
+                .help("Brainfuck source file(s) to run")
+                .required(true)
+                .num_args(1..),
+        )
+        .arg(
+            Arg::new("eof")
+                .long("eof")
+                .value_name("zero|max|unchanged")
+                .help("What the `,` opcode writes once input is exhausted")
+                .value_parser(["zero", "max", "unchanged"])
+                .default_value("zero"),
+        )
+        .arg(
+            Arg::new("cell-size")
+                .long("cell-size")
+                .value_name("8|16|32")
+                .help("Bit width of each tape cell")
+                .value_parser(["8", "16", "32"])
+                .default_value("8"),
+        )
+        .arg(
+            Arg::new("wide-io")
+                .long("wide-io")
+                .help("`.`/`,` move the full cell width little-endian instead of just the low byte")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("emit")
+                .long("emit")
+                .value_name("c|rust")
+                .help(
+                    "Transpile to source instead of interpreting, and print it to stdout. \
+                     Honors --eof/--wide-io, but unlike the interpreter the emitted tape is a \
+                     fixed 30000-cell buffer starting in the middle: shifting more than 15000 \
+                     cells past either end is undefined behavior in C / a panic in Rust",
+                )
+                .value_parser(["c", "rust"]),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .short('q')
+                .help(
+                    "Never print the \"Running {path}\" banner (by default it's printed only \
+                     when running more than one file)",
+                )
+                .action(ArgAction::SetTrue)
+                .conflicts_with("verbose"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .help("Always print a \"Running {path}\" banner before each file")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("quiet"),
+        )
+        .get_matches();
+
+    let paths = matches
+        .get_many::<String>("path")
+        .unwrap()
+        .map(PathBuf::from)
+        .collect();
+
+    let eof = match matches.get_one::<String>("eof").unwrap().as_str() {
+        "zero" => EofBehavior::Zero,
+        "max" => EofBehavior::Max,
+        "unchanged" => EofBehavior::Unchanged,
+        _ => unreachable!("value_parser restricts this to zero|max|unchanged"),
+    };
+
+    let cell_width = match matches.get_one::<String>("cell-size").unwrap().as_str() {
+        "8" => CellWidth::Eight,
+        "16" => CellWidth::Sixteen,
+        "32" => CellWidth::ThirtyTwo,
+        _ => unreachable!("value_parser restricts this to 8|16|32"),
+    };
+
+    let emit = match matches.get_one::<String>("emit").map(|s| s.as_str()) {
+        Some("c") => Some(Lang::C),
+        Some("rust") => Some(Lang::Rust),
+        Some(_) => unreachable!("value_parser restricts this to c|rust"),
+        None => None,
+    };
+
+    Cli {
+        paths,
+        cell_width,
+        options: Options {
+            eof,
+            wide_io: matches.get_flag("wide-io"),
+        },
+        verbose: matches.get_flag("verbose"),
+        quiet: matches.get_flag("quiet"),
+        emit,
+    }
+}
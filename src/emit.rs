@@ -0,0 +1,425 @@
+use crate::interp::{EofBehavior, Options};
+use crate::opcode::{compile_jump_table, Opcode};
+
+/// Target language for `--emit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    C,
+    Rust,
+}
+
+/// Cells in the fixed tape buffer the generated program allocates. The
+/// length is the same for every `--cell-size`, which only picks the element
+/// type.
+///
+/// Unlike `Tape`, this buffer cannot grow, so `p` starts at `TAPE_SIZE / 2`
+/// instead of at the origin (the convention most BF-to-C transpilers use),
+/// letting ordinary left-of-origin programs run as they would under the
+/// interpreter's bidirectionally-growable tape. A program that wanders more
+/// than `TAPE_SIZE / 2` cells past either end is still out of luck: that's
+/// UB in the generated C and a panic in the generated Rust.
+const TAPE_SIZE: usize = 30_000;
+
+/// Turns the optimized opcode stream into a standalone source file that
+/// performs the same computation natively, instead of interpreting it.
+///
+/// `options` is honored so the emitted program's `.`/`,` behavior matches
+/// the interpreter's for the same flags: `wide_io` moves the full cell width
+/// little-endian instead of just the low byte, and `eof` picks what a `,`
+/// past the end of input writes.
+pub fn emit(opcodes: &[Opcode], lang: Lang, cell_bits: u32, options: Options) -> String {
+    match lang {
+        Lang::C => emit_c(opcodes, cell_bits, options),
+        Lang::Rust => emit_rust(opcodes, cell_bits, options),
+    }
+}
+
+fn c_cell_type(cell_bits: u32) -> &'static str {
+    match cell_bits {
+        8 => "uint8_t",
+        16 => "uint16_t",
+        32 => "uint32_t",
+        _ => unreachable!("cell width is always 8, 16, or 32"),
+    }
+}
+
+/// The statement that runs when a C `,` hits end-of-input, matching
+/// `interp::Options::eof`. Casting `-1` to the (unsigned) cell type yields
+/// its all-ones bit pattern, i.e. its max value.
+fn c_eof_action(eof: EofBehavior, cell_type: &str) -> String {
+    match eof {
+        EofBehavior::Zero => "*p = 0;".to_string(),
+        EofBehavior::Max => format!("*p = ({cell_type})-1;"),
+        EofBehavior::Unchanged => String::new(),
+    }
+}
+
+/// Bundles the pieces of emitter state that stay fixed across a recursive
+/// `emit_*_range` call, so the range functions don't need a long argument
+/// list just to thread them through loop bodies.
+struct EmitCtx<'a> {
+    opcodes: &'a [Opcode],
+    table: &'a [usize],
+    cell_type: &'a str,
+    options: Options,
+}
+
+fn emit_c(opcodes: &[Opcode], cell_bits: u32, options: Options) -> String {
+    let cell_type = c_cell_type(cell_bits);
+    let table = compile_jump_table(opcodes).expect("Mismatched brackets");
+    let ctx = EmitCtx { opcodes, table: &table, cell_type, options };
+
+    let mut out = String::new();
+    out.push_str("#include <stdint.h>\n#include <stdio.h>\n\n");
+    out.push_str(
+        "// NOTE: unlike the interpreter's bidirectionally-growable tape, this\n\
+         // buffer is fixed-size; `p` starts in the middle so ordinary `<`\n\
+         // usage works, but wandering more than TAPE_SIZE/2 cells past\n\
+         // either end is undefined behavior, unlike under `brainrust`\n\
+         // without `--emit`.\n",
+    );
+    out.push_str(&format!("static {} tape[{}];\n\n", cell_type, TAPE_SIZE));
+    out.push_str("int main(void) {\n");
+    out.push_str(&format!("    {} *p = tape + {}/2;\n", cell_type, TAPE_SIZE));
+    emit_c_range(&ctx, 0, opcodes.len(), 1, &mut out);
+    out.push_str("    return 0;\n}\n");
+    out
+}
+
+fn emit_c_range(ctx: &EmitCtx, mut i: usize, end: usize, depth: usize, out: &mut String) {
+    let indent = "    ".repeat(depth);
+    let cell_type = ctx.cell_type;
+    let bytes = c_cell_bytes(cell_type);
+    let eof_action = c_eof_action(ctx.options.eof, cell_type);
+    while i < end {
+        match ctx.opcodes[i] {
+            Opcode::Add(n) => out.push_str(&format!("{indent}*p += ({cell_type}){n};\n")),
+            Opcode::Sub(n) => out.push_str(&format!("{indent}*p -= ({cell_type}){n};\n")),
+            Opcode::ShiftRight(n) => out.push_str(&format!("{indent}p += {n};\n")),
+            Opcode::ShiftLeft(n) => out.push_str(&format!("{indent}p -= {n};\n")),
+            Opcode::Print => {
+                if ctx.options.wide_io {
+                    out.push_str(&format!(
+                        "{indent}{{ {cell_type} v = *p; for (int i = 0; i < {bytes}; i++) {{ putchar((int)(unsigned char)(v & 0xFF)); v >>= 8; }} }}\n"
+                    ));
+                } else {
+                    out.push_str(&format!("{indent}putchar(*p);\n"));
+                }
+            },
+            Opcode::Input => {
+                if ctx.options.wide_io {
+                    out.push_str(&format!(
+                        "{indent}{{ {cell_type} v = 0; int ok = 1; for (int i = 0; i < {bytes}; i++) {{ int c = getchar(); if (c == EOF) {{ ok = 0; break; }} v |= (({cell_type})c) << (8 * i); }} if (ok) {{ *p = v; }} else {{ {eof_action} }} }}\n"
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "{indent}{{ int c = getchar(); if (c == EOF) {{ {eof_action} }} else {{ *p = ({cell_type})c; }} }}\n"
+                    ));
+                }
+            },
+            Opcode::Clear => out.push_str(&format!("{indent}*p = 0;\n")),
+            Opcode::MulAdd { offset, factor } => out.push_str(&format!(
+                "{indent}p[{offset}] += ({cell_type})({factor}) * (*p);\n"
+            )),
+            Opcode::ScanRight(n) => out.push_str(&format!("{indent}while (*p) p += {n};\n")),
+            Opcode::ScanLeft(n) => out.push_str(&format!("{indent}while (*p) p -= {n};\n")),
+            Opcode::BeginLoop => {
+                let loop_end = ctx.table[i];
+                out.push_str(&format!("{indent}while (*p) {{\n"));
+                emit_c_range(ctx, i + 1, loop_end, depth + 1, out);
+                out.push_str(&format!("{indent}}}\n"));
+                i = loop_end;
+            },
+            Opcode::EndLoop => unreachable!("ranges are bounded by matching EndLoop indices"),
+        }
+        i += 1;
+    }
+}
+
+fn c_cell_bytes(cell_type: &str) -> u32 {
+    match cell_type {
+        "uint8_t" => 1,
+        "uint16_t" => 2,
+        "uint32_t" => 4,
+        _ => unreachable!("cell width is always 8, 16, or 32"),
+    }
+}
+
+fn rust_cell_type(cell_bits: u32) -> &'static str {
+    match cell_bits {
+        8 => "u8",
+        16 => "u16",
+        32 => "u32",
+        _ => unreachable!("cell width is always 8, 16, or 32"),
+    }
+}
+
+/// The statement that runs when a Rust `,` hits end-of-input, matching
+/// `interp::Options::eof`.
+fn rust_eof_action(eof: EofBehavior, cell_type: &str) -> String {
+    match eof {
+        EofBehavior::Zero => "tape[p] = 0;".to_string(),
+        EofBehavior::Max => format!("tape[p] = {cell_type}::MAX;"),
+        EofBehavior::Unchanged => String::new(),
+    }
+}
+
+fn emit_rust(opcodes: &[Opcode], cell_bits: u32, options: Options) -> String {
+    let cell_type = rust_cell_type(cell_bits);
+    let table = compile_jump_table(opcodes).expect("Mismatched brackets");
+    let ctx = EmitCtx { opcodes, table: &table, cell_type, options };
+
+    let mut out = String::new();
+    out.push_str("use std::io::{Read, Write};\n\n");
+    out.push_str(
+        "// NOTE: unlike the interpreter's bidirectionally-growable tape, this\n\
+         // buffer is fixed-size; `p` starts in the middle so ordinary `<`\n\
+         // usage works, but wandering more than TAPE_SIZE/2 cells past\n\
+         // either end panics, unlike under `brainrust` without `--emit`.\n",
+    );
+    out.push_str("fn main() {\n");
+    out.push_str(&format!("    let mut tape = [0{cell_type}; {TAPE_SIZE}];\n"));
+    out.push_str(&format!("    let mut p: usize = {TAPE_SIZE} / 2;\n"));
+    out.push_str("    let mut stdout = std::io::stdout();\n");
+    emit_rust_range(&ctx, 0, opcodes.len(), 1, &mut out);
+    out.push_str("}\n");
+    out
+}
+
+fn rust_cell_bytes(cell_type: &str) -> u32 {
+    match cell_type {
+        "u8" => 1,
+        "u16" => 2,
+        "u32" => 4,
+        _ => unreachable!("cell width is always 8, 16, or 32"),
+    }
+}
+
+fn emit_rust_range(ctx: &EmitCtx, mut i: usize, end: usize, depth: usize, out: &mut String) {
+    let indent = "    ".repeat(depth);
+    let cell_type = ctx.cell_type;
+    let bytes = rust_cell_bytes(cell_type);
+    let eof_action = rust_eof_action(ctx.options.eof, cell_type);
+    while i < end {
+        match ctx.opcodes[i] {
+            Opcode::Add(n) => out.push_str(&format!(
+                "{indent}tape[p] = tape[p].wrapping_add({n}u32 as {cell_type});\n"
+            )),
+            Opcode::Sub(n) => out.push_str(&format!(
+                "{indent}tape[p] = tape[p].wrapping_sub({n}u32 as {cell_type});\n"
+            )),
+            Opcode::ShiftRight(n) => out.push_str(&format!("{indent}p += {n};\n")),
+            Opcode::ShiftLeft(n) => out.push_str(&format!("{indent}p -= {n};\n")),
+            Opcode::Print => {
+                if ctx.options.wide_io {
+                    out.push_str(&format!(
+                        "{indent}stdout.write_all(&tape[p].to_le_bytes()).unwrap();\n"
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "{indent}stdout.write_all(&[tape[p] as u8]).unwrap();\n"
+                    ));
+                }
+            },
+            Opcode::Input => {
+                if ctx.options.wide_io {
+                    out.push_str(&format!(
+                        "{indent}{{ let mut b = [0u8; {bytes}]; match std::io::stdin().read_exact(&mut b) {{ Ok(()) => {{ tape[p] = {cell_type}::from_le_bytes(b); }}, Err(_) => {{ {eof_action} }} }} }}\n"
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "{indent}{{ let mut b = [0u8; 1]; match std::io::stdin().read_exact(&mut b) {{ Ok(()) => {{ tape[p] = b[0] as {cell_type}; }}, Err(_) => {{ {eof_action} }} }} }}\n"
+                    ));
+                }
+            },
+            Opcode::Clear => out.push_str(&format!("{indent}tape[p] = 0;\n")),
+            Opcode::MulAdd { offset, factor } => out.push_str(&format!(
+                "{indent}{{ let idx = (p as i64 + {offset}) as usize; tape[idx] = tape[idx].wrapping_add(tape[p].wrapping_mul({factor}i64 as {cell_type})); }}\n"
+            )),
+            Opcode::ScanRight(n) => out.push_str(&format!("{indent}while tape[p] != 0 {{ p += {n}; }}\n")),
+            Opcode::ScanLeft(n) => out.push_str(&format!("{indent}while tape[p] != 0 {{ p -= {n}; }}\n")),
+            Opcode::BeginLoop => {
+                let loop_end = ctx.table[i];
+                out.push_str(&format!("{indent}while tape[p] != 0 {{\n"));
+                emit_rust_range(ctx, i + 1, loop_end, depth + 1, out);
+                out.push_str(&format!("{indent}}}\n"));
+                i = loop_end;
+            },
+            Opcode::EndLoop => unreachable!("ranges are bounded by matching EndLoop indices"),
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcode::{compile_opcodes, optimize_loops};
+    use std::process::Command;
+
+    const HELLO_WORLD_SRC: &str = "++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.>>.<-.<.+++.------.--------.>>+.>++.";
+
+    const DEFAULT_OPTIONS: Options = Options { eof: EofBehavior::Zero, wide_io: false };
+
+    fn unique_path(suffix: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("brainrust_emit_test_{}{}", std::process::id(), suffix))
+    }
+
+    #[test]
+    fn emits_c_hello_world_and_it_runs() {
+        let opcodes = optimize_loops(&compile_opcodes(HELLO_WORLD_SRC));
+        let source = emit(&opcodes, Lang::C, 8, DEFAULT_OPTIONS);
+
+        let src_path = unique_path(".c");
+        let bin_path = unique_path(".bin");
+        std::fs::write(&src_path, source).expect("failed to write generated C source");
+
+        let status = Command::new("cc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke cc");
+        assert!(status.success(), "cc failed to compile the generated program");
+
+        let output = Command::new(&bin_path).output().expect("failed to run compiled program");
+        assert_eq!(output.stdout, b"Hello World!\n");
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    #[test]
+    fn emits_rust_hello_world_and_it_runs() {
+        let opcodes = optimize_loops(&compile_opcodes(HELLO_WORLD_SRC));
+        let source = emit(&opcodes, Lang::Rust, 8, DEFAULT_OPTIONS);
+
+        let src_path = unique_path(".rs");
+        let bin_path = unique_path("_rs.bin");
+        std::fs::write(&src_path, source).expect("failed to write generated Rust source");
+
+        let status = Command::new("rustc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke rustc");
+        assert!(status.success(), "rustc failed to compile the generated program");
+
+        let output = Command::new(&bin_path).output().expect("failed to run compiled program");
+        assert_eq!(output.stdout, b"Hello World!\n");
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    /// `,.` on an empty stdin with `--cell-size 16 --wide-io --eof max`
+    /// should read nothing, fall back to the max cell value, and print it as
+    /// two little-endian bytes — exactly what the interpreter does for the
+    /// same flags.
+    #[test]
+    fn emits_c_honoring_wide_io_and_eof_options() {
+        let opcodes = optimize_loops(&compile_opcodes(",."));
+        let options = Options { eof: EofBehavior::Max, wide_io: true };
+        let source = emit(&opcodes, Lang::C, 16, options);
+
+        let src_path = unique_path("_wide.c");
+        let bin_path = unique_path("_wide.bin");
+        std::fs::write(&src_path, source).expect("failed to write generated C source");
+
+        let status = Command::new("cc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke cc");
+        assert!(status.success(), "cc failed to compile the generated program");
+
+        let output = Command::new(&bin_path)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("failed to run compiled program");
+        assert_eq!(output.stdout, vec![0xFF, 0xFF]);
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    #[test]
+    fn emits_rust_honoring_wide_io_and_eof_options() {
+        let opcodes = optimize_loops(&compile_opcodes(",."));
+        let options = Options { eof: EofBehavior::Max, wide_io: true };
+        let source = emit(&opcodes, Lang::Rust, 16, options);
+
+        let src_path = unique_path("_wide.rs");
+        let bin_path = unique_path("_wide_rs.bin");
+        std::fs::write(&src_path, source).expect("failed to write generated Rust source");
+
+        let status = Command::new("rustc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke rustc");
+        assert!(status.success(), "rustc failed to compile the generated program");
+
+        let output = Command::new(&bin_path)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("failed to run compiled program");
+        assert_eq!(output.stdout, vec![0xFF, 0xFF]);
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    /// `<` before any `>` is ordinary, valid Brainfuck — `p` must start with
+    /// room to its left, not at the first cell of the buffer.
+    #[test]
+    fn emits_c_surviving_a_shift_left_before_any_shift_right() {
+        let opcodes = optimize_loops(&compile_opcodes("<+."));
+        let source = emit(&opcodes, Lang::C, 8, DEFAULT_OPTIONS);
+
+        let src_path = unique_path("_left.c");
+        let bin_path = unique_path("_left.bin");
+        std::fs::write(&src_path, source).expect("failed to write generated C source");
+
+        let status = Command::new("cc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke cc");
+        assert!(status.success(), "cc failed to compile the generated program");
+
+        let output = Command::new(&bin_path).output().expect("failed to run compiled program");
+        assert_eq!(output.stdout, vec![1]);
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    #[test]
+    fn emits_rust_surviving_a_shift_left_before_any_shift_right() {
+        let opcodes = optimize_loops(&compile_opcodes("<+."));
+        let source = emit(&opcodes, Lang::Rust, 8, DEFAULT_OPTIONS);
+
+        let src_path = unique_path("_left.rs");
+        let bin_path = unique_path("_left_rs.bin");
+        std::fs::write(&src_path, source).expect("failed to write generated Rust source");
+
+        let status = Command::new("rustc")
+            .arg(&src_path)
+            .arg("-o")
+            .arg(&bin_path)
+            .status()
+            .expect("failed to invoke rustc");
+        assert!(status.success(), "rustc failed to compile the generated program");
+
+        let output = Command::new(&bin_path).output().expect("failed to run compiled program");
+        assert_eq!(output.stdout, vec![1]);
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+}
@@ -0,0 +1,203 @@
+use std::io;
+use std::io::{BufReader, Read, Write};
+
+use crate::cell::Cell;
+use crate::opcode::{compile_jump_table, compile_opcodes, optimize_loops, Opcode};
+use crate::tape::Tape;
+
+/// What a `,` opcode does once the input stream is exhausted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EofBehavior {
+    /// Write a 0 to the current cell.
+    Zero,
+    /// Write the cell width's max value (255/65535/4294967295) to the
+    /// current cell.
+    Max,
+    /// Leave the current cell untouched.
+    Unchanged,
+}
+
+/// Options controlling how a program is interpreted, independent of which
+/// cell width it runs at.
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub eof: EofBehavior,
+    /// When true, `.`/`,` move the full cell width little-endian instead of
+    /// just its low byte.
+    pub wide_io: bool,
+}
+
+pub fn brainf<C: Cell>(src: &str, options: Options) {
+    // A wrapper around the brainf interpreter, passing stdin/stdout to it.
+    // Buffered once here rather than re-locking stdin on every `,`.
+    let mut stdin = BufReader::new(io::stdin());
+    brainf_output::<C>(src, &mut io::stdout(), &mut stdin, options);
+}
+
+pub fn brainf_output<C: Cell>(
+    src: &str,
+    stdout: &mut dyn Write,
+    stdin: &mut dyn Read,
+    options: Options,
+) {
+    let mut tape: Tape<C> = Tape::new();
+
+    let opcodes = optimize_loops(&compile_opcodes(src));
+    let mut program_counter: usize = 0;
+
+    let table = compile_jump_table(&opcodes).expect("Mismatched brackets");
+
+    while program_counter < opcodes.len() {
+        match opcodes[program_counter] {
+            Opcode::Add(v) => tape.set(tape.get().wrapping_offset(v as i64)),
+            Opcode::Sub(v) => tape.set(tape.get().wrapping_offset(-(v as i64))),
+            Opcode::ShiftLeft(shift) => tape.shift_left(shift),
+            Opcode::ShiftRight(shift) => tape.shift_right(shift),
+            Opcode::Print => {
+                let bytes = if options.wide_io {
+                    tape.get().to_le_bytes()
+                } else {
+                    vec![tape.get().low_byte()]
+                };
+                stdout.write_all(&bytes).expect("Could not write");
+                stdout.flush().unwrap();
+            },
+            Opcode::Input => {
+                let width = if options.wide_io { C::BYTES } else { 1 };
+                let mut buf = vec![0u8; width];
+                match stdin.read_exact(&mut buf) {
+                    Ok(()) => {
+                        let value = if options.wide_io {
+                            C::from_le_bytes(&buf)
+                        } else {
+                            C::from_low_byte(buf[0])
+                        };
+                        tape.set(value);
+                    },
+                    Err(_) => match options.eof {
+                        EofBehavior::Zero => tape.set(C::default()),
+                        EofBehavior::Max => tape.set(C::MAX),
+                        EofBehavior::Unchanged => {},
+                    },
+                }
+            },
+            Opcode::BeginLoop => {
+                if tape.get().is_zero() {
+                    program_counter = table[program_counter];
+                }
+            },
+            Opcode::EndLoop => {
+                if !tape.get().is_zero() {
+                    program_counter = table[program_counter];
+                }
+            },
+            Opcode::Clear => tape.set(C::default()),
+            Opcode::MulAdd { offset, factor } => {
+                let delta = factor * tape.get().as_i64();
+                tape.set_at(offset, tape.get_at(offset).wrapping_offset(delta));
+            },
+            Opcode::ScanRight(n) => tape.scan_right(n),
+            Opcode::ScanLeft(n) => tape.scan_left(n),
+        }
+        program_counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_OPTIONS: Options = Options { eof: EofBehavior::Zero, wide_io: false };
+
+    #[test]
+    fn prints_hello_world() {
+        let src = ">+++IGNORED BY INTERPRETER+++++[<+++++++++>-]<.>++++[<+++++REDUNDANT COMMENT!!!++>-]<+.+++++++..+++.>>++++++[<+++++++>-]<+
+        +.------------.>++++++[<+++++++++>-]<+.<.+++.------.-IGNORED BY INTERPRETER-------.>>>++++[<++++++++>-
+        ]<+.";
+
+        let mut output = Vec::new();
+        brainf_output::<u8>(src, &mut output, &mut io::empty(), DEFAULT_OPTIONS);
+        assert_eq!(output, b"Hello, World!");
+    }
+
+    #[test]
+    fn skips_loop_at_beginning() {
+        let src = "[+.]";
+        let mut output = Vec::new();
+        brainf_output::<u8>(src, &mut output, &mut io::empty(), DEFAULT_OPTIONS);
+        assert_eq!(output.len(), 0);
+    }
+
+    #[test]
+    fn halting_loop_behavior() {
+        brainf::<u8>("++[-]", DEFAULT_OPTIONS); // does not halt!
+        brainf::<u8>("--[+]", DEFAULT_OPTIONS); // <--
+        brainf::<u8>(">++[-<->]<[+]", DEFAULT_OPTIONS);
+    }
+
+    #[test]
+    fn cell_width_changes_whether_256_increments_wrap_to_zero() {
+        let src = format!("{}[-.]", "+".repeat(256));
+
+        // An 8-bit cell wraps to 0 after 256 increments, so the loop never runs.
+        let mut output8 = Vec::new();
+        brainf_output::<u8>(&src, &mut output8, &mut io::empty(), DEFAULT_OPTIONS);
+        assert_eq!(output8.len(), 0);
+
+        // A 16-bit cell is still 256, so the loop counts all the way down.
+        let mut output16 = Vec::new();
+        brainf_output::<u16>(&src, &mut output16, &mut io::empty(), DEFAULT_OPTIONS);
+        assert_eq!(output16.len(), 256);
+        assert_eq!(output16[0], 255);
+        assert_eq!(output16[255], 0);
+    }
+
+    #[test]
+    fn input_reads_from_the_injected_stdin() {
+        let mut output = Vec::new();
+        brainf_output::<u8>(",.", &mut output, &mut &b"A"[..], DEFAULT_OPTIONS);
+        assert_eq!(output, b"A");
+    }
+
+    #[test]
+    fn eof_zero_writes_the_default_value() {
+        let options = Options { eof: EofBehavior::Zero, wide_io: false };
+        let mut output = Vec::new();
+        brainf_output::<u8>(",.", &mut output, &mut io::empty(), options);
+        assert_eq!(output, vec![0]);
+    }
+
+    #[test]
+    fn eof_max_writes_the_cell_widths_max_value() {
+        let options = Options { eof: EofBehavior::Max, wide_io: false };
+        let mut output = Vec::new();
+        brainf_output::<u8>(",.", &mut output, &mut io::empty(), options);
+        assert_eq!(output, vec![255]);
+    }
+
+    #[test]
+    fn eof_unchanged_leaves_the_cell_untouched() {
+        let options = Options { eof: EofBehavior::Unchanged, wide_io: false };
+        // Set the cell to a known nonzero value first, then hit EOF on `,`.
+        let mut output = Vec::new();
+        brainf_output::<u8>("+++++,.", &mut output, &mut io::empty(), options);
+        assert_eq!(output, vec![5]);
+    }
+
+    #[test]
+    fn wide_io_reads_and_writes_the_full_cell_width_little_endian() {
+        let options = Options { eof: EofBehavior::Zero, wide_io: true };
+        let mut output = Vec::new();
+        // 0x1234 little-endian is the bytes [0x34, 0x12].
+        brainf_output::<u16>(",.", &mut output, &mut &[0x34u8, 0x12][..], options);
+        assert_eq!(output, vec![0x34, 0x12]);
+    }
+
+    #[test]
+    fn wide_io_eof_max_writes_all_bytes_of_the_max_value() {
+        let options = Options { eof: EofBehavior::Max, wide_io: true };
+        let mut output = Vec::new();
+        brainf_output::<u16>(",.", &mut output, &mut io::empty(), options);
+        assert_eq!(output, vec![0xFF, 0xFF]);
+    }
+}
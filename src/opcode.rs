@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    /*
+    These opcodes may carry a number of times to be executed.
+    Some loops may require a lot of arithmetic that can be simplified.
+    Add/Sub and Left/Right opcodes use a runlength encoding that groups the largest
+    group of adjacent like instructions, and finds their net effect.
+    There is no need to optimize empty loops as they will never be run anyways.
+    */
+    // A run-length count, widened beyond the 8-bit cell width so that a long
+    // run of `+`/`-` still has the right net effect at 16- or 32-bit widths.
+    Add(u32),
+    Sub(u32),
+    ShiftLeft(usize),
+    ShiftRight(usize),
+    Print,
+    Input,
+    BeginLoop,
+    EndLoop,
+    // The opcodes below are never produced by `compile_opcodes` itself; they
+    // are lowered from recognizable loop idioms by `optimize_loops`.
+    /// Sets the current cell to 0 directly, in place of a `[-]`/`[+]` loop.
+    Clear,
+    /// `tape[pos + offset] += factor * tape[pos]`, using the value `tape[pos]`
+    /// had *before* the loop it replaces started clearing it. Always followed
+    /// by a `Clear` for the original loop body to have taken effect.
+    MulAdd { offset: i64, factor: i64 },
+    /// Repeatedly steps by `n` cells to the right until landing on a zero
+    /// cell, in place of a `[>n]`-style scan loop.
+    ScanRight(usize),
+    /// Mirror image of `ScanRight`.
+    ScanLeft(usize),
+}
+
+pub fn compile_opcodes(src: &str) -> Vec<Opcode> {
+    let mut opcodes = Vec::new();
+    let mut src = String::from(src);
+    src.retain(|c| "+-<>[].,".contains(c));
+
+    for m in Regex::new(r"[-+]+|[<>]+|\.|,|\[|\]").unwrap().find_iter(src.as_str()) {
+        let match_str = m.as_str().as_bytes();
+        match match_str[0] {
+            b'+' | b'-' => {
+                let num_minus = match_str.iter().filter(|b| **b == b'-').count();
+                let num_plus = match_str.len() - num_minus;
+                // num_plus = len - num_minus
+                // net = num_plus - num_minus = (len - num_minus) - num_minus = len - 2num_minus
+                if num_plus != num_minus {
+                    opcodes.push(
+                        if num_plus > num_minus {
+                            Opcode::Add((num_plus - num_minus) as u32)
+                        } else {
+                            Opcode::Sub((num_minus - num_plus) as u32)
+                        }
+                    );
+                }
+            },
+            b'<' | b'>' => {
+                let num_left = match_str.iter().filter(|b| **b == b'<').count();
+                let num_right = match_str.len() - num_left;
+                if num_right != num_left {
+                    opcodes.push(
+                        if num_right > num_left {
+                            Opcode::ShiftRight(num_right - num_left)
+                        } else {
+                            Opcode::ShiftLeft(num_left - num_right)
+                        }
+                    );
+                }
+            },
+            b'.' => opcodes.push(Opcode::Print),
+            b',' => opcodes.push(Opcode::Input),
+            b'[' => opcodes.push(Opcode::BeginLoop),
+            b']' => opcodes.push(Opcode::EndLoop),
+            _ => {},
+        }
+    }
+    opcodes
+}
+
+pub fn compile_jump_table(src: &[Opcode]) -> Result<Vec<usize>, String> {
+    let mut table = vec![0; src.len()];
+    let mut stack = Vec::new();
+
+    for i in 0..src.len() {
+        match src[i] {
+            Opcode::BeginLoop => stack.push(i),
+            Opcode::EndLoop => {
+                match stack.pop() {
+                    Some(left_bracket) => {
+                        table[left_bracket] = i;
+                        table[i] = left_bracket;
+                    },
+                    None => return Err(format!("Mismatched ']' at {}", i)),
+                }
+            },
+            _ => {},
+        }
+    }
+
+    if stack.is_empty() {
+        Ok(table)
+    } else {
+        Err(format!("{} too many '['", stack.len()))
+    }
+}
+
+/// A second compilation pass that recognizes common loop idioms — clear,
+/// multiply/copy, and scan loops — and lowers them to the dedicated opcodes
+/// above. Must run before `compile_jump_table`, since it changes indices.
+pub fn optimize_loops(opcodes: &[Opcode]) -> Vec<Opcode> {
+    let table = compile_jump_table(opcodes).expect("Mismatched brackets");
+
+    let mut out = Vec::with_capacity(opcodes.len());
+    let mut i = 0;
+    while i < opcodes.len() {
+        if opcodes[i] != Opcode::BeginLoop {
+            out.push(opcodes[i]);
+            i += 1;
+            continue;
+        }
+
+        let end = table[i];
+        let body = &opcodes[i + 1..end];
+        if let Some(replacement) = lower_clear_loop(body)
+            .or_else(|| lower_scan_loop(body))
+            .or_else(|| lower_mul_add_loop(body))
+        {
+            out.extend(replacement);
+        } else {
+            // Not a recognized idiom: keep the loop, but still optimize its
+            // body so that nested clear/scan/multiply loops are caught.
+            out.push(Opcode::BeginLoop);
+            out.extend(optimize_loops(body));
+            out.push(Opcode::EndLoop);
+        }
+        i = end + 1;
+    }
+    out
+}
+
+fn lower_clear_loop(body: &[Opcode]) -> Option<Vec<Opcode>> {
+    match body {
+        [Opcode::Add(1)] | [Opcode::Sub(1)] => Some(vec![Opcode::Clear]),
+        _ => None,
+    }
+}
+
+fn lower_scan_loop(body: &[Opcode]) -> Option<Vec<Opcode>> {
+    match body {
+        [Opcode::ShiftRight(n)] => Some(vec![Opcode::ScanRight(*n)]),
+        [Opcode::ShiftLeft(n)] => Some(vec![Opcode::ScanLeft(*n)]),
+        _ => None,
+    }
+}
+
+/// Recognizes a "balanced" loop body — only `Add`/`Sub`/`ShiftLeft`/`ShiftRight`,
+/// no I/O or nested loops — that returns the pointer to where it started and
+/// decrements the counter cell by exactly 1 per iteration. Lowers it to one
+/// `MulAdd` per other cell it touches, followed by a `Clear` of the counter.
+fn lower_mul_add_loop(body: &[Opcode]) -> Option<Vec<Opcode>> {
+    let mut offset: i64 = 0;
+    let mut deltas: BTreeMap<i64, i64> = BTreeMap::new();
+
+    for op in body {
+        match *op {
+            Opcode::Add(v) => *deltas.entry(offset).or_insert(0) += v as i64,
+            Opcode::Sub(v) => *deltas.entry(offset).or_insert(0) -= v as i64,
+            Opcode::ShiftRight(n) => offset += n as i64,
+            Opcode::ShiftLeft(n) => offset -= n as i64,
+            // I/O or a nested loop makes this not a pure arithmetic loop.
+            _ => return None,
+        }
+    }
+
+    // The pointer must end up back where the loop started.
+    if offset != 0 {
+        return None;
+    }
+    // Termination is only guaranteed if the counter cell decreases by
+    // exactly 1 per iteration.
+    if deltas.get(&0).copied().unwrap_or(0) != -1 {
+        return None;
+    }
+
+    let mut out: Vec<Opcode> = deltas
+        .into_iter()
+        .filter(|&(off, delta)| off != 0 && delta != 0)
+        .map(|(off, delta)| Opcode::MulAdd { offset: off, factor: delta })
+        .collect();
+    out.push(Opcode::Clear);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brackets_table_works() {
+        let table = compile_jump_table(&compile_opcodes("++[-][]")).ok().unwrap();
+        let expected = [0, 3, 0, 1, 5, 4];
+        assert_eq!(table, expected);
+
+        let table = compile_jump_table(&compile_opcodes("[]")).ok().unwrap();
+        let expected = [1, 0];
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn opcodes_get_simplified() {
+        assert_eq!(
+            compile_opcodes(">++-[-],.<"),
+            vec![
+                Opcode::ShiftRight(1),
+                Opcode::Add(1),
+                Opcode::BeginLoop,
+                Opcode::Sub(1),
+                Opcode::EndLoop,
+                Opcode::Input,
+                Opcode::Print,
+                Opcode::ShiftLeft(1),
+            ]
+        );
+
+        assert_eq!(
+            compile_opcodes(""),
+            vec![]
+        );
+
+        assert_eq!(
+            compile_opcodes("+-[[---]],..."),
+            vec![
+                Opcode::BeginLoop,
+                Opcode::BeginLoop,
+                Opcode::Sub(3),
+                Opcode::EndLoop,
+                Opcode::EndLoop,
+                Opcode::Input,
+                Opcode::Print,
+                Opcode::Print,
+                Opcode::Print,
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_loop_becomes_clear_opcode() {
+        assert_eq!(optimize_loops(&compile_opcodes("[-]")), vec![Opcode::Clear]);
+        assert_eq!(optimize_loops(&compile_opcodes("[+]")), vec![Opcode::Clear]);
+    }
+
+    #[test]
+    fn scan_loop_becomes_scan_opcode() {
+        assert_eq!(optimize_loops(&compile_opcodes("[>>>]")), vec![Opcode::ScanRight(3)]);
+        assert_eq!(optimize_loops(&compile_opcodes("[<]")), vec![Opcode::ScanLeft(1)]);
+    }
+
+    #[test]
+    fn copy_loop_becomes_mul_add_and_clear() {
+        // Classic "copy cell 0 into cells 1 and 2" idiom.
+        assert_eq!(
+            optimize_loops(&compile_opcodes("[->+>+<<]")),
+            vec![
+                Opcode::MulAdd { offset: 1, factor: 1 },
+                Opcode::MulAdd { offset: 2, factor: 1 },
+                Opcode::Clear,
+            ]
+        );
+    }
+
+    #[test]
+    fn unbalanced_or_non_arithmetic_loops_are_left_alone() {
+        // Pointer does not return to where it started.
+        let unbalanced = compile_opcodes("[->+]");
+        assert_eq!(optimize_loops(&unbalanced), unbalanced);
+
+        // Contains I/O, so it cannot be a pure arithmetic loop.
+        let has_io = compile_opcodes("[.-]");
+        assert_eq!(optimize_loops(&has_io), has_io);
+    }
+
+    #[test]
+    fn nested_clear_loop_is_still_found() {
+        assert_eq!(
+            optimize_loops(&compile_opcodes("[>[-]<-]")),
+            vec![
+                Opcode::BeginLoop,
+                Opcode::ShiftRight(1),
+                Opcode::Clear,
+                Opcode::ShiftLeft(1),
+                Opcode::Sub(1),
+                Opcode::EndLoop,
+            ]
+        );
+    }
+}
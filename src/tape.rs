@@ -0,0 +1,247 @@
+use crate::cell::Cell;
+
+/// Cells per chunk. Keeps a chunk allocation within one to four pages (for
+/// 8- to 32-bit cells) while still amortizing the cost of growing the tape.
+const CHUNK_SIZE: usize = 4096;
+
+/// A sparse, bidirectionally-growable tape of cells.
+///
+/// Cells are addressed by a signed logical position so the tape can grow to
+/// the left of the starting cell just as cheaply as to the right. Storage is
+/// split into fixed-size chunks that are allocated lazily: reading a cell
+/// that was never written returns the zero value without allocating
+/// anything, and the backing `Vec` only grows when a cell outside the
+/// chunks currently held is written. Generic over the cell width (`u8`/`u16`/`u32`) so
+/// `--cell-size` can pick the tape's element type at startup.
+pub struct Tape<C: Cell> {
+    chunks: Vec<Option<Box<[C; CHUNK_SIZE]>>>,
+    // Logical chunk index of `chunks[0]`.
+    base_chunk: i64,
+    pos: i64,
+}
+
+impl<C: Cell> Tape<C> {
+    pub fn new() -> Self {
+        Tape {
+            chunks: vec![None],
+            base_chunk: 0,
+            pos: 0,
+        }
+    }
+
+    fn split(pos: i64) -> (i64, usize) {
+        let chunk = pos.div_euclid(CHUNK_SIZE as i64);
+        let inner = pos.rem_euclid(CHUNK_SIZE as i64) as usize;
+        (chunk, inner)
+    }
+
+    /// Grows `self.chunks` if needed so that `chunk` has a slot, and returns
+    /// its index into `self.chunks`. Never allocates the chunk itself.
+    fn ensure_slot(&mut self, chunk: i64) -> usize {
+        if chunk < self.base_chunk {
+            let grow_by = (self.base_chunk - chunk) as usize;
+            let mut front = Vec::with_capacity(grow_by);
+            front.resize_with(grow_by, || None);
+            front.append(&mut self.chunks);
+            self.chunks = front;
+            self.base_chunk = chunk;
+        } else {
+            let last = self.base_chunk + self.chunks.len() as i64 - 1;
+            if chunk > last {
+                let grow_by = (chunk - last) as usize;
+                self.chunks.resize_with(self.chunks.len() + grow_by, || None);
+            }
+        }
+        (chunk - self.base_chunk) as usize
+    }
+
+    pub fn get(&self) -> C {
+        self.get_at(0)
+    }
+
+    pub fn set(&mut self, value: C) {
+        self.set_at(0, value);
+    }
+
+    /// Reads the cell `offset` away from the current position, without
+    /// moving the tape. Used by the `MulAdd` opcode to reach the cells a
+    /// multiply/copy loop targets.
+    pub fn get_at(&self, offset: i64) -> C {
+        let (chunk, inner) = Self::split(self.pos + offset);
+        let idx = chunk - self.base_chunk;
+        if idx < 0 || idx as usize >= self.chunks.len() {
+            return C::default();
+        }
+        match &self.chunks[idx as usize] {
+            Some(data) => data[inner],
+            None => C::default(),
+        }
+    }
+
+    pub fn set_at(&mut self, offset: i64, value: C) {
+        let (chunk, inner) = Self::split(self.pos + offset);
+        let slot = self.ensure_slot(chunk);
+        let data = self.chunks[slot].get_or_insert_with(|| Box::new([C::default(); CHUNK_SIZE]));
+        data[inner] = value;
+    }
+
+    pub fn shift_left(&mut self, amount: usize) {
+        self.pos -= amount as i64;
+    }
+
+    pub fn shift_right(&mut self, amount: usize) {
+        self.pos += amount as i64;
+    }
+
+    /// Advances by `stride` repeatedly until landing on a zero cell, as if
+    /// running a `[>>>]`-style scan loop. The stride-1 case is accelerated
+    /// via `Cell::find_zero_from` (which `memchr`s for `u8` cells).
+    pub fn scan_right(&mut self, stride: usize) {
+        if stride != 1 {
+            while !self.get().is_zero() {
+                self.pos += stride as i64;
+            }
+            return;
+        }
+        loop {
+            let (chunk, inner) = Self::split(self.pos);
+            let idx = chunk - self.base_chunk;
+            if idx < 0 || idx as usize >= self.chunks.len() {
+                return;
+            }
+            match &self.chunks[idx as usize] {
+                None => return,
+                Some(data) => match C::find_zero_from(&data[..], inner) {
+                    Some(found) => {
+                        self.pos += found as i64;
+                        return;
+                    }
+                    None => self.pos += (CHUNK_SIZE - inner) as i64,
+                },
+            }
+        }
+    }
+
+    /// Mirror image of [`Tape::scan_right`], moving towards negative
+    /// positions.
+    pub fn scan_left(&mut self, stride: usize) {
+        if stride != 1 {
+            while !self.get().is_zero() {
+                self.pos -= stride as i64;
+            }
+            return;
+        }
+        loop {
+            let (chunk, inner) = Self::split(self.pos);
+            let idx = chunk - self.base_chunk;
+            if idx < 0 || idx as usize >= self.chunks.len() {
+                return;
+            }
+            match &self.chunks[idx as usize] {
+                None => return,
+                Some(data) => match C::find_zero_before(&data[..], inner) {
+                    Some(found) => {
+                        self.pos -= (inner - found) as i64;
+                        return;
+                    }
+                    None => self.pos -= (inner + 1) as i64,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unwritten_cells_are_zero() {
+        let tape: Tape<u8> = Tape::new();
+        assert_eq!(tape.get(), 0);
+    }
+
+    #[test]
+    fn set_then_get_round_trips() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.set(42);
+        assert_eq!(tape.get(), 42);
+    }
+
+    #[test]
+    fn wider_cells_round_trip_too() {
+        let mut tape: Tape<u32> = Tape::new();
+        tape.set(0xdeadbeef);
+        assert_eq!(tape.get(), 0xdeadbeef);
+    }
+
+    #[test]
+    fn get_at_and_set_at_do_not_move_the_tape() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.set_at(3, 5);
+        assert_eq!(tape.get(), 0);
+        assert_eq!(tape.get_at(3), 5);
+    }
+
+    #[test]
+    fn shifting_left_past_zero_grows_backwards() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.set(1);
+        tape.shift_left(10_000);
+        assert_eq!(tape.get(), 0);
+        tape.set(2);
+        tape.shift_right(10_000);
+        assert_eq!(tape.get(), 1);
+    }
+
+    #[test]
+    fn shifting_across_many_chunk_boundaries_preserves_values() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.set(7);
+        tape.shift_right(CHUNK_SIZE * 3 + 5);
+        tape.set(9);
+        tape.shift_left(CHUNK_SIZE * 3 + 5);
+        assert_eq!(tape.get(), 7);
+        tape.shift_right(CHUNK_SIZE * 3 + 5);
+        assert_eq!(tape.get(), 9);
+    }
+
+    #[test]
+    fn scan_right_stops_on_first_zero_cell() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.set(1);
+        tape.shift_right(1);
+        tape.set(1);
+        tape.shift_right(1);
+        tape.set(0);
+        tape.shift_left(2);
+        tape.scan_right(1);
+        assert_eq!(tape.get(), 0);
+    }
+
+    #[test]
+    fn scan_left_stops_on_first_zero_cell() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.shift_right(1);
+        tape.set(1);
+        tape.shift_right(1);
+        tape.set(1);
+        // cell 0 is still the default zero; scanning left from cell 2 should
+        // walk past both nonzero cells and land there.
+        tape.scan_left(1);
+        assert_eq!(tape.get(), 0);
+    }
+
+    #[test]
+    fn scan_with_stride_only_looks_at_stride_aligned_cells() {
+        let mut tape: Tape<u8> = Tape::new();
+        tape.shift_right(2);
+        tape.set(5); // nonzero, but not on a multiple-of-3 offset: must be skipped over
+        tape.shift_left(2);
+        tape.set(1);
+        tape.scan_right(3);
+        assert_eq!(tape.get(), 0);
+        tape.shift_left(3);
+        assert_eq!(tape.get(), 1);
+    }
+}